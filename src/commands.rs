@@ -0,0 +1,82 @@
+// cli arg/subcommand definitions
+use clap::{App, Arg, SubCommand};
+
+pub const REPO_FILE_ARG: &str = "repo_file";
+pub const DRY_RUN_ARG: [&str; 4] = ["dry-run", "d", "dry-run", "print what would be done instead of doing it"];
+pub const VERBOSE_ARG: [&str; 4] = ["verbose", "v", "verbose", "print more information about what is happening"];
+pub const REBASE_ARG: [&str; 4] = ["rebase", "r", "rebase", "rebase the output branch onto the branch you started from"];
+pub const TOPBASE_ARG: [&str; 4] = ["topbase", "t", "topbase", "topbase flag; it is parsed and stored, but the out and in subcommands run no topbase step"];
+pub const OUTPUT_BRANCH_ARG: [&str; 4] = ["output-branch", "o", "output-branch", "name of the branch to create with the split history"];
+pub const SSH_KEY_ARG: [&str; 4] = ["ssh-key", "s", "ssh-key", "path to an ssh private key to use when fetching from the remote"];
+pub const WORKTREE_ARG: [&str; 4] = ["worktree", "w", "worktree", "perform the split in a temporary linked worktree instead of the current checkout"];
+pub const MANIFEST_ARG: &str = "manifest";
+pub const JOBS_ARG: [&str; 4] = ["jobs", "j", "jobs", "number of repos from the manifest to split concurrently"];
+
+fn repo_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(REPO_FILE_ARG)
+        .required(true)
+        .help("path to the repo_file describing the split")
+}
+
+fn dry_run_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(DRY_RUN_ARG[0]).short(DRY_RUN_ARG[1]).long(DRY_RUN_ARG[2]).help(DRY_RUN_ARG[3])
+}
+
+fn verbose_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(VERBOSE_ARG[0]).short(VERBOSE_ARG[1]).long(VERBOSE_ARG[2]).help(VERBOSE_ARG[3])
+}
+
+fn rebase_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(REBASE_ARG[0]).short(REBASE_ARG[1]).long(REBASE_ARG[2]).help(REBASE_ARG[3])
+}
+
+fn topbase_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(TOPBASE_ARG[0]).short(TOPBASE_ARG[1]).long(TOPBASE_ARG[2]).help(TOPBASE_ARG[3])
+}
+
+fn output_branch_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(OUTPUT_BRANCH_ARG[0]).short(OUTPUT_BRANCH_ARG[1]).long(OUTPUT_BRANCH_ARG[2])
+        .takes_value(true).help(OUTPUT_BRANCH_ARG[3])
+}
+
+fn ssh_key_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(SSH_KEY_ARG[0]).short(SSH_KEY_ARG[1]).long(SSH_KEY_ARG[2])
+        .takes_value(true).help(SSH_KEY_ARG[3])
+}
+
+fn worktree_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(WORKTREE_ARG[0]).short(WORKTREE_ARG[1]).long(WORKTREE_ARG[2]).help(WORKTREE_ARG[3])
+}
+
+fn jobs_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(JOBS_ARG[0]).short(JOBS_ARG[1]).long(JOBS_ARG[2])
+        .takes_value(true).help(JOBS_ARG[3])
+}
+
+fn common_split_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(repo_file_arg())
+        .arg(dry_run_arg())
+        .arg(verbose_arg())
+        .arg(rebase_arg())
+        .arg(topbase_arg())
+        .arg(output_branch_arg())
+        .arg(ssh_key_arg())
+        .arg(worktree_arg())
+}
+
+pub fn split_in<'a, 'b>() -> App<'a, 'b> {
+    common_split_args(SubCommand::with_name("in").about("merge a previously split-out repo back in"))
+}
+
+pub fn split_out<'a, 'b>() -> App<'a, 'b> {
+    common_split_args(SubCommand::with_name("out").about("split a subdirectory out into its own branch/repo"))
+}
+
+pub fn split_manifest<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("manifest")
+        .about("split many repos in one invocation, as described by a manifest file")
+        .arg(Arg::with_name(MANIFEST_ARG).required(true).help("path to the manifest file, one repo_file path per line"))
+        .arg(dry_run_arg())
+        .arg(verbose_arg())
+        .arg(jobs_arg())
+}
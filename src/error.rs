@@ -0,0 +1,40 @@
+// crate-wide error type used by Runner and friends
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("missing dependency '{0}'. is it installed and on your PATH?")]
+    MissingDependency(String),
+
+    #[error("you have modified changes. please stash or commit your changes before running this command")]
+    DirtyWorkingTree,
+
+    #[error("failed to execute '{}': {stderr}", args.join(" "))]
+    FilterRepoFailed { args: Vec<String>, stderr: String },
+
+    #[error("failed to rebase: {0}")]
+    RebaseFailed(String),
+
+    #[error("failed to merge: {0}")]
+    MergeConflict(String),
+
+    #[error("failed to parse remote: {0}")]
+    RemoteParse(String),
+
+    #[error("{0} is invalid. Must be an even length array of strings (or a single string, where that is allowed)")]
+    InvalidArg(String),
+
+    #[error("manifest run failed: {0}")]
+    ManifestFailed(String),
+
+    #[error("failed to find repository: {0}")]
+    RepoNotFound(String),
+
+    #[error("failed to change to repository root {0}")]
+    ChangeRepoRoot(String),
+
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,154 @@
+// native libgit2 fetch/pull, used instead of shelling out to `git pull`/`git merge`
+use std::path::Path;
+use git2::Cred;
+use git2::FetchOptions;
+use git2::RemoteCallbacks;
+use git2::Repository;
+use git2::AnnotatedCommit;
+
+use super::error::Error;
+use super::error::Result;
+
+// try, in order:
+//   1. ssh-agent, for the url's username (or "git" if none was given)
+//   2. an explicit ssh key path, if the caller provided one
+//   3. username/password from GIT_USERNAME/GIT_PASSWORD env vars, for http(s)
+fn make_credentials_callback<'a>(
+    ssh_key_path: Option<&'a str>,
+) -> impl FnMut(&str, Option<&str>, git2::CredentialType) -> std::result::Result<Cred, git2::Error> + 'a {
+    move |url, username_from_url, allowed_types| {
+        let username = username_from_url.unwrap_or("git");
+
+        if allowed_types.contains(git2::CredentialType::SSH_KEY) {
+            if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                return Ok(cred);
+            }
+            if let Some(key_path) = ssh_key_path {
+                return Cred::ssh_key(username, None, Path::new(key_path), None);
+            }
+        }
+
+        if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            if let (Ok(user), Ok(pass)) = (
+                std::env::var("GIT_USERNAME"),
+                std::env::var("GIT_PASSWORD"),
+            ) {
+                return Cred::userpass_plaintext(&user, &pass);
+            }
+        }
+
+        Err(git2::Error::from_str(&format!(
+            "no valid credentials found for {}", url,
+        )))
+    }
+}
+
+// fetch `remote_branch` (or the remote's default branch) from `remote_repo`
+// into `repo`, printing transfer progress as objects come in, and return
+// the resulting AnnotatedCommit so the caller can merge/fast-forward it
+pub fn fetch<'a>(
+    repo: &'a Repository,
+    remote_repo: &str,
+    remote_branch: Option<&str>,
+    ssh_key_path: Option<&str>,
+) -> Result<AnnotatedCommit<'a>> {
+    let mut remote = repo.remote_anonymous(remote_repo)?;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(make_credentials_callback(ssh_key_path));
+    callbacks.transfer_progress(|stats| {
+        if stats.received_objects() == stats.total_objects() {
+            print!(
+                "Resolving deltas {}/{}\r",
+                stats.indexed_deltas(), stats.total_deltas(),
+            );
+        } else if stats.total_objects() > 0 {
+            print!(
+                "Received {}/{} objects ({} bytes, {} local)\r",
+                stats.received_objects(),
+                stats.total_objects(),
+                stats.received_bytes(),
+                stats.local_objects(),
+            );
+        }
+        true
+    });
+
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(callbacks);
+
+    let refspec = match remote_branch {
+        Some(branch) => format!("refs/heads/{branch}"),
+        None => "HEAD".into(),
+    };
+    remote.fetch(&[refspec.as_str()], Some(&mut fetch_opts), None)?;
+    println!();
+
+    let fetch_head = repo.find_reference("FETCH_HEAD")?;
+    let commit = repo.reference_to_annotated_commit(&fetch_head)?;
+    Ok(commit)
+}
+
+// fast-forward the branch currently checked out in `repo` to `annotated`
+fn fast_forward(repo: &Repository, annotated: &AnnotatedCommit) -> Result<()> {
+    let head = repo.head()?;
+    let branch_name = match head.name() {
+        Some(name) => name.to_string(),
+        None => return Err(Error::MergeConflict("HEAD is not pointing at a branch".into())),
+    };
+    let mut branch_ref = repo.find_reference(&branch_name)?;
+    let msg = format!("fast-forward: setting {} to {}", branch_name, annotated.id());
+    branch_ref.set_target(annotated.id(), &msg)?;
+    repo.set_head(&branch_name)?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+    Ok(())
+}
+
+// merge `annotated` into the branch currently checked out in `repo`,
+// creating a merge commit with both parents
+fn normal_merge(repo: &Repository, local: &AnnotatedCommit, annotated: &AnnotatedCommit) -> Result<()> {
+    let local_tree = repo.find_commit(local.id())?.tree()?;
+    let remote_tree = repo.find_commit(annotated.id())?.tree()?;
+    let ancestor = repo.find_commit(repo.merge_base(local.id(), annotated.id())?)?.tree()?;
+    let mut idx = repo.merge_trees(&ancestor, &local_tree, &remote_tree, None)?;
+
+    if idx.has_conflicts() {
+        repo.checkout_index(Some(&mut idx), None)?;
+        return Err(Error::MergeConflict("merge produced conflicts".into()));
+    }
+
+    let result_tree = repo.find_tree(idx.write_tree_to(repo)?)?;
+    let sig = repo.signature()?;
+    let local_commit = repo.find_commit(local.id())?;
+    let remote_commit = repo.find_commit(annotated.id())?;
+    let msg = format!("Merge {} into {}", annotated.id(), local.id());
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        &msg,
+        &result_tree,
+        &[&local_commit, &remote_commit],
+    )?;
+    repo.checkout_head(None)?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+// fast-forward (or merge, if a fast-forward isn't possible) `annotated`
+// into the branch currently checked out in `repo`
+pub fn merge_fetched_commit(repo: &Repository, annotated: &AnnotatedCommit) -> Result<()> {
+    let analysis = repo.merge_analysis(&[annotated])?;
+
+    if analysis.0.is_up_to_date() {
+        return Ok(());
+    }
+
+    if analysis.0.is_fast_forward() {
+        return fast_forward(repo, annotated);
+    }
+
+    let head = repo.head()?;
+    let local = repo.reference_to_annotated_commit(&head)?;
+    normal_merge(repo, &local, annotated)
+}
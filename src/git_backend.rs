@@ -0,0 +1,193 @@
+// abstracts the git operations a split needs, so tests can swap in a
+// MockBackend instead of a live repo. checkout_orphan and fetch go
+// through git2 directly (see split.rs and fetch.rs) and aren't part
+// of this trait
+use super::error::Error;
+use super::error::Result;
+use super::exec_helpers;
+
+pub trait GitBackend {
+    fn run_filter(&mut self, args: &[&str]) -> Result<()>;
+    fn rebase(&mut self, upstream: &str, global_args: &[String]) -> Result<()>;
+    fn ls_files_modified(&mut self, global_args: &[String]) -> Result<Vec<String>>;
+}
+
+// the real backend: shells out via exec_helpers, same as Runner always has
+pub struct ExecBackend;
+
+impl GitBackend for ExecBackend {
+    fn run_filter(&mut self, args: &[&str]) -> Result<()> {
+        match exec_helpers::execute(args) {
+            Ok(o) if o.status == 0 => Ok(()),
+            Ok(o) => Err(Error::FilterRepoFailed {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                stderr: o.stderr,
+            }),
+            Err(e) => Err(Error::FilterRepoFailed {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                stderr: format!("{}", e),
+            }),
+        }
+    }
+
+    fn rebase(&mut self, upstream: &str, global_args: &[String]) -> Result<()> {
+        let mut args = vec!["git".to_string()];
+        args.extend(global_args.iter().cloned());
+        args.push("rebase".to_string());
+        args.push(upstream.to_string());
+        let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        match exec_helpers::execute(&arg_refs) {
+            Ok(o) if o.status == 0 => Ok(()),
+            Ok(o) => Err(Error::RebaseFailed(o.stderr.lines().next().unwrap_or("").to_string())),
+            Err(e) => Err(Error::RebaseFailed(format!("{}", e))),
+        }
+    }
+
+    fn ls_files_modified(&mut self, global_args: &[String]) -> Result<Vec<String>> {
+        let mut args = vec!["git".to_string()];
+        args.extend(global_args.iter().cloned());
+        args.push("ls-files".to_string());
+        args.push("--modified".to_string());
+        let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        match exec_helpers::execute(&arg_refs) {
+            Ok(o) if o.status == 0 => Ok(o.stdout.lines().map(|l| l.to_string()).collect()),
+            Ok(_) | Err(_) => Err(Error::DirtyWorkingTree),
+        }
+    }
+}
+
+#[cfg(test)]
+pub struct MockBackend {
+    // shared so a test can keep a handle to inspect invocations after
+    // moving the backend into a Runner
+    pub invocations: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    pub ls_files_modified_result: Vec<String>,
+    pub fail_next_filter: bool,
+}
+
+#[cfg(test)]
+impl Default for MockBackend {
+    fn default() -> Self {
+        MockBackend {
+            invocations: Default::default(),
+            ls_files_modified_result: Vec::new(),
+            fail_next_filter: false,
+        }
+    }
+}
+
+#[cfg(test)]
+impl GitBackend for MockBackend {
+    fn run_filter(&mut self, args: &[&str]) -> Result<()> {
+        self.invocations.borrow_mut().push(args.join(" "));
+        if self.fail_next_filter {
+            return Err(Error::FilterRepoFailed {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                stderr: "mocked failure".into(),
+            });
+        }
+        Ok(())
+    }
+
+    fn rebase(&mut self, upstream: &str, global_args: &[String]) -> Result<()> {
+        let mut invocation = vec!["git".to_string()];
+        invocation.extend(global_args.iter().cloned());
+        invocation.push("rebase".to_string());
+        invocation.push(upstream.to_string());
+        self.invocations.borrow_mut().push(invocation.join(" "));
+        Ok(())
+    }
+
+    fn ls_files_modified(&mut self, global_args: &[String]) -> Result<Vec<String>> {
+        let mut invocation = vec!["git".to_string()];
+        invocation.extend(global_args.iter().cloned());
+        invocation.push("ls-files".to_string());
+        invocation.push("--modified".to_string());
+        self.invocations.borrow_mut().push(invocation.join(" "));
+        Ok(self.ls_files_modified_result.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::split::generate_filter_arg_vec;
+    use super::super::split::Runner;
+
+    #[test]
+    fn mock_backend_records_filter_invocations() {
+        let args = vec!["--path".to_string(), "mysubdir".to_string()];
+        let global_args = vec![];
+        let arg_vec = generate_filter_arg_vec(&args, "my-output-branch", &global_args);
+
+        let mut backend = MockBackend::default();
+        backend.run_filter(&arg_vec).unwrap();
+
+        assert_eq!(backend.invocations.borrow().len(), 1);
+        assert_eq!(
+            backend.invocations.borrow()[0],
+            "git filter-repo --path mysubdir --refs my-output-branch --force",
+        );
+    }
+
+    #[test]
+    fn mock_backend_records_global_args_when_rooted_elsewhere() {
+        let args = vec!["--path".to_string(), "mysubdir".to_string()];
+        let global_args = vec!["-C".to_string(), "/tmp/some-worktree".to_string()];
+        let arg_vec = generate_filter_arg_vec(&args, "my-output-branch", &global_args);
+
+        let mut backend = MockBackend::default();
+        backend.run_filter(&arg_vec).unwrap();
+
+        assert_eq!(
+            backend.invocations.borrow()[0],
+            "git -C /tmp/some-worktree filter-repo --path mysubdir --refs my-output-branch --force",
+        );
+    }
+
+    #[test]
+    fn mock_backend_can_script_a_failure() {
+        let args = vec!["--path".to_string(), "mysubdir".to_string()];
+        let global_args = vec![];
+        let arg_vec = generate_filter_arg_vec(&args, "my-output-branch", &global_args);
+
+        let mut backend = MockBackend {
+            fail_next_filter: true,
+            ..Default::default()
+        };
+        let result = backend.run_filter(&arg_vec);
+
+        assert!(result.is_err());
+        assert_eq!(backend.invocations.borrow().len(), 1);
+    }
+
+    // drives a real `Runner` (not just a bare MockBackend) through
+    // filter_include_as and rebase, with no git or git-filter-repo on
+    // PATH, proving the backend abstraction is actually load-bearing
+    #[test]
+    fn runner_drives_filter_include_as_and_rebase_through_mock_backend() {
+        let app = super::super::commands::split_out();
+        let matches = app.get_matches_from(vec!["out", "dummy_repo_file"]);
+        let mut runner = Runner::new(&matches);
+
+        let backend = MockBackend::default();
+        let invocations = backend.invocations.clone();
+        runner = runner.with_backend(Box::new(backend));
+
+        runner.output_branch = Some("my-output-branch".into());
+        runner.include_as_arg_str = Some(vec!["subdir".into(), "".into()]);
+        runner.repo_original_ref = Some("refs/heads/main".into());
+        runner.git_global_args = vec!["-C".into(), "/tmp/some-worktree".into()];
+
+        let runner = runner.filter_include_as().unwrap();
+        let _runner = runner.rebase().unwrap();
+
+        let recorded = invocations.borrow();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(
+            recorded[0],
+            "git -C /tmp/some-worktree filter-repo subdir  --refs my-output-branch --force",
+        );
+        assert_eq!(recorded[1], "git -C /tmp/some-worktree rebase main");
+    }
+}
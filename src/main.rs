@@ -1,7 +1,14 @@
-use git2::Repository;
 use clap::{App, ArgMatches};
 
 mod commands;
+mod error;
+mod fetch;
+mod remote_type;
+mod git_backend;
+mod split;
+mod manifest;
+
+use error::Error;
 
 fn get_cli_input<'a>() -> ArgMatches<'a> {
     let mut base_app = App::new(env!("CARGO_PKG_NAME"))
@@ -11,22 +18,88 @@ fn get_cli_input<'a>() -> ArgMatches<'a> {
 
     base_app = base_app.subcommands(vec![
         commands::split_in(),
-        commands::split_out()
+        commands::split_out(),
+        commands::split_manifest(),
     ]);
 
     return base_app.get_matches();
 }
 
-fn main() {
+// run either the "out" (split a subdirectory out into its own branch) or
+// "in" (merge a previously split-out repo back in) subcommand against a
+// real Runner pipeline
+fn run_split_subcommand(submatches: &ArgMatches, is_output: bool) -> Result<(), Error> {
+    let mut runner = split::Runner::new(submatches)
+        .save_current_dir()?
+        .get_repository_from_current_dir()?
+        .get_repo_file()?
+        .verify_dependencies()?
+        .change_to_repo_root()?
+        .safe_to_proceed()?
+        .save_current_ref()?
+        .make_temporary_worktree()?;
+
+    runner = if is_output {
+        let output_branch = runner.output_branch.clone().ok_or_else(|| {
+            Error::RepoNotFound("missing required --output-branch argument".into())
+        })?;
+        runner.make_and_checkout_orphan_branch(&output_branch)?
+            .filter_include()?
+            .filter_include_as()?
+            .filter_exclude()?
+    } else {
+        runner.populate_empty_branch_with_remote_commits()?
+    };
+
+    if runner.should_rebase {
+        runner = runner.rebase()?;
+    }
+
+    runner.remove_temporary_worktree()?;
+    Ok(())
+}
+
+fn run_manifest_subcommand(submatches: &ArgMatches) -> Result<(), Error> {
+    let manifest_path = submatches.value_of(commands::MANIFEST_ARG).ok_or_else(|| {
+        Error::ManifestFailed("missing required manifest argument".into())
+    })?;
+    let jobs: usize = submatches.value_of(commands::JOBS_ARG[0])
+        .and_then(|j| j.parse().ok())
+        .unwrap_or(1);
+    let dry_run = submatches.is_present(commands::DRY_RUN_ARG[0]);
+    let verbose = submatches.is_present(commands::VERBOSE_ARG[0]);
+
+    let parsed = manifest::parse_manifest_file(manifest_path)?;
+    let outcomes = manifest::run_manifest(&parsed, jobs, dry_run, verbose);
+    manifest::print_summary(&outcomes);
+
+    if outcomes.iter().any(|o| !o.success) {
+        return Err(Error::ManifestFailed("one or more repos in the manifest failed to split".into()));
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), Error> {
     let matches = get_cli_input();
 
-    if let Some(submatches) = matches.subcommand_matches("split") {
-        let iterator = submatches.value_of("repo_file").unwrap_or("");
-        println!("{:?}", iterator);
+    if let Some(submatches) = matches.subcommand_matches("out") {
+        return run_split_subcommand(submatches, true);
     }
 
-    let repo = match Repository::discover(".") {
-        Ok(repo) => repo,
-        Err(e) => panic!("failed to open: {}", e),
-    };
+    if let Some(submatches) = matches.subcommand_matches("in") {
+        return run_split_subcommand(submatches, false);
+    }
+
+    if let Some(submatches) = matches.subcommand_matches("manifest") {
+        return run_manifest_subcommand(submatches);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,176 @@
+// manifest mode: split many subrepos in one invocation by iterating a
+// manifest file of repo_file paths
+use std::fs;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+use super::error::Error;
+use super::error::Result;
+
+pub struct ManifestEntry {
+    pub repo_file_path: String,
+}
+
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+// parse a manifest file: one repo_file path per line. blank lines and
+// lines starting with '#' are ignored
+pub fn parse_manifest_file(manifest_path: &str) -> Result<Manifest> {
+    let contents = fs::read_to_string(manifest_path).map_err(|e| {
+        Error::RepoNotFound(format!("failed to read manifest '{}': {}", manifest_path, e))
+    })?;
+
+    let entries = contents
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| ManifestEntry { repo_file_path: l.to_string() })
+        .collect();
+
+    Ok(Manifest { entries })
+}
+
+pub struct RepoOutcome {
+    pub repo_file_path: String,
+    pub success: bool,
+    pub message: String,
+}
+
+// run a single repo_file entry out-of-process, by re-invoking this
+// same binary's `out` subcommand against it with --worktree, so
+// concurrent entries never collide
+fn run_entry(exe: &str, repo_file_path: &str, dry_run: bool, verbose: bool) -> RepoOutcome {
+    let mut cmd = Command::new(exe);
+    cmd.arg("out")
+        .arg(repo_file_path)
+        .arg("--worktree");
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    if verbose {
+        cmd.arg("--verbose");
+    }
+
+    match cmd.output() {
+        Ok(o) if o.status.success() => RepoOutcome {
+            repo_file_path: repo_file_path.to_string(),
+            success: true,
+            message: "ok".into(),
+        },
+        Ok(o) => RepoOutcome {
+            repo_file_path: repo_file_path.to_string(),
+            success: false,
+            message: String::from_utf8_lossy(&o.stderr).trim().to_string(),
+        },
+        Err(e) => RepoOutcome {
+            repo_file_path: repo_file_path.to_string(),
+            success: false,
+            message: format!("{}", e),
+        },
+    }
+}
+
+// run every entry in the manifest, reporting a per-repo success/failure
+// summary. when jobs > 1, up to `jobs` entries run concurrently
+pub fn run_manifest(manifest: &Manifest, jobs: usize, dry_run: bool, verbose: bool) -> Vec<RepoOutcome> {
+    let exe = std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "git-monorepo-tools".into());
+
+    if jobs <= 1 {
+        return manifest.entries.iter()
+            .map(|e| run_entry(&exe, &e.repo_file_path, dry_run, verbose))
+            .collect();
+    }
+
+    let queue = Arc::new(Mutex::new(
+        manifest.entries.iter().map(|e| e.repo_file_path.clone()).collect::<Vec<_>>()
+    ));
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let mut handles = Vec::new();
+
+    for _ in 0..jobs {
+        let queue = Arc::clone(&queue);
+        let results = Arc::clone(&results);
+        let exe = exe.clone();
+        handles.push(thread::spawn(move || {
+            loop {
+                let next = queue.lock().unwrap().pop();
+                let repo_file_path = match next {
+                    Some(p) => p,
+                    None => break,
+                };
+                let outcome = run_entry(&exe, &repo_file_path, dry_run, verbose);
+                results.lock().unwrap().push(outcome);
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    Arc::try_unwrap(results).unwrap().into_inner().unwrap()
+}
+
+// how many outcomes succeeded, out of the total
+fn count_successes(outcomes: &[RepoOutcome]) -> (usize, usize) {
+    let succeeded = outcomes.iter().filter(|o| o.success).count();
+    (succeeded, outcomes.len())
+}
+
+// print the per-repo success/failure summary
+pub fn print_summary(outcomes: &[RepoOutcome]) {
+    for outcome in outcomes {
+        let status = if outcome.success { "ok" } else { "FAILED" };
+        println!("{}: {}", outcome.repo_file_path, status);
+        if !outcome.success {
+            println!("  {}", outcome.message);
+        }
+    }
+    let (succeeded, total) = count_successes(outcomes);
+    println!("{}/{} succeeded", succeeded, total);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn parse_manifest_file_skips_blank_and_comment_lines() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("gmt-test-manifest-{}.txt", std::process::id()));
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(file, "# a comment").unwrap();
+        writeln!(file, "").unwrap();
+        writeln!(file, "repo_file_one").unwrap();
+        writeln!(file, "   ").unwrap();
+        writeln!(file, "repo_file_two").unwrap();
+
+        let manifest = parse_manifest_file(path.to_str().unwrap()).unwrap();
+        let paths: Vec<&str> = manifest.entries.iter().map(|e| e.repo_file_path.as_str()).collect();
+        assert_eq!(paths, vec!["repo_file_one", "repo_file_two"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn parse_manifest_file_errors_on_missing_file() {
+        let result = parse_manifest_file("/no/such/manifest/file.txt");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn count_successes_counts_failures_separately() {
+        let outcomes = vec![
+            RepoOutcome { repo_file_path: "a".into(), success: true, message: "ok".into() },
+            RepoOutcome { repo_file_path: "b".into(), success: false, message: "boom".into() },
+            RepoOutcome { repo_file_path: "c".into(), success: true, message: "ok".into() },
+        ];
+        assert_eq!(count_successes(&outcomes), (2, 3));
+    }
+}
@@ -0,0 +1,101 @@
+// classifies what kind of remote a repo string refers to, and pulls a
+// normalized repo name out of it
+use std::path::MAIN_SEPARATOR;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RemoteType {
+    Ssh,
+    Https,
+    Http,
+    Git,
+    Ftp,
+    File,
+    Local,
+}
+
+// classify a remote_repo string. returns None if it doesn't look like
+// any of the recognized remote shapes
+pub fn parse_remote_type(remote_repo: &str) -> Option<RemoteType> {
+    if remote_repo.starts_with("https://") {
+        return Some(RemoteType::Https);
+    }
+    if remote_repo.starts_with("http://") {
+        return Some(RemoteType::Http);
+    }
+    if remote_repo.starts_with("git://") {
+        return Some(RemoteType::Git);
+    }
+    if remote_repo.starts_with("ssh://") {
+        return Some(RemoteType::Ssh);
+    }
+    if remote_repo.starts_with("ftp://") || remote_repo.starts_with("sftp://") {
+        return Some(RemoteType::Ftp);
+    }
+    if remote_repo.starts_with("file://") {
+        return Some(RemoteType::File);
+    }
+    if remote_repo.starts_with('.') || remote_repo.starts_with('/') {
+        return Some(RemoteType::Local);
+    }
+    if is_scp_like(remote_repo) {
+        return Some(RemoteType::Ssh);
+    }
+    None
+}
+
+pub fn is_valid_remote_repo(remote_repo: &str) -> bool {
+    parse_remote_type(remote_repo).is_some()
+}
+
+// recognizes scp-style ssh shorthand like `git@github.com:org/repo.git`
+// or `user@server.com:/gitpath`. deliberately requires the host portion
+// (between '@' and ':') to be non-empty and free of slashes, so that a
+// path like `some@dir/file:name` isn't mistaken for it. windows-style
+// paths like `.\Desktop\reponame` or `C:\path` contain no '@' and never match
+fn is_scp_like(remote_repo: &str) -> bool {
+    let after_at = match remote_repo.splitn(2, '@').nth(1) {
+        Some(s) if !s.is_empty() => s,
+        _ => return false,
+    };
+    match after_at.find(':') {
+        Some(idx) => idx > 0 && !after_at[..idx].contains('/') && !after_at[..idx].contains('\\'),
+        None => false,
+    }
+}
+
+// pull the bare repo name (no trailing `.git`, no path) out of the
+// scp-style path portion, e.g. `org/repo.git` -> `repo`
+fn repo_name_from_scp_path(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    let name = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    name.split('.').next().unwrap_or(name).to_string()
+}
+
+// try to pull a normalized repo name out of remote_repo, given its
+// already-classified RemoteType. returns None if no name could be found
+pub fn repo_name_for_type(remote_repo: &str, remote_type: RemoteType) -> Option<String> {
+    if remote_type == RemoteType::Ssh && is_scp_like(remote_repo) {
+        let path = remote_repo.splitn(2, ':').nth(1)?;
+        let name = repo_name_from_scp_path(path);
+        return if name.is_empty() { None } else { Some(name) };
+    }
+
+    let slash_type = MAIN_SEPARATOR;
+    let next_slash_type = if slash_type == '/' { '\\' } else { '/' };
+    let name = repo_name_with_slash_type(remote_repo, slash_type)
+        .or_else(|| repo_name_with_slash_type(remote_repo, next_slash_type));
+    name.filter(|n| !n.is_empty())
+}
+
+fn repo_name_with_slash_type(remote_repo: &str, slash_type: char) -> Option<String> {
+    let mut out_str = remote_repo.trim_end().to_string();
+    if out_str.ends_with(slash_type) {
+        out_str.pop();
+    }
+    if !out_str.contains(slash_type) {
+        return None;
+    }
+    let name = out_str.rsplit(slash_type).next().unwrap_or(&out_str);
+    let name = name.split('.').next().unwrap_or(name);
+    Some(name.to_string())
+}
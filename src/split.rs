@@ -2,7 +2,6 @@
 // and running a split-X command
 use std::env;
 use std::path::PathBuf;
-use std::path::MAIN_SEPARATOR;
 use clap::ArgMatches;
 
 use super::commands::REPO_FILE_ARG;
@@ -11,10 +10,19 @@ use super::commands::VERBOSE_ARG;
 use super::commands::REBASE_ARG;
 use super::commands::TOPBASE_ARG;
 use super::commands::OUTPUT_BRANCH_ARG;
+use super::commands::SSH_KEY_ARG;
+use super::commands::WORKTREE_ARG;
 use super::repo_file;
 use super::repo_file::RepoFile;
 use super::git_helpers;
 use super::exec_helpers;
+use super::error::Error;
+use super::error::Result;
+use super::fetch;
+use super::remote_type;
+use super::remote_type::RemoteType;
+use super::git_backend::GitBackend;
+use super::git_backend::ExecBackend;
 
 pub struct Runner<'a> {
     pub repo_file_path: Option<&'a str>,
@@ -36,6 +44,18 @@ pub struct Runner<'a> {
     pub include_arg_str: Option<Vec<String>>,
     pub include_as_arg_str: Option<Vec<String>>,
     pub exclude_arg_str: Option<Vec<String>>,
+    pub ssh_key_path: Option<String>,
+    // when set, the split runs in a temporary linked worktree instead
+    // of mutating the user's current checkout
+    pub use_worktree: bool,
+    pub worktree_dir: Option<PathBuf>,
+    // persistent `-C <path>` args (set by change_to_repo_root and
+    // make_temporary_worktree) prepended to every git invocation below,
+    // instead of relying on the process's current directory
+    pub git_global_args: Vec<String>,
+    // the git operations a split performs, abstracted so tests can
+    // swap in a MockBackend instead of shelling out to a live repo
+    pub backend: Box<dyn GitBackend>,
     pub status: i32,
 }
 
@@ -47,6 +67,8 @@ impl<'a> Runner<'a> {
         let is_topbase = matches.is_present(TOPBASE_ARG[0]);
         let output_branch = matches.value_of(OUTPUT_BRANCH_ARG[0]);
         let repo_file_path = matches.value_of(REPO_FILE_ARG);
+        let ssh_key_path = matches.value_of(SSH_KEY_ARG[0]);
+        let use_worktree = matches.is_present(WORKTREE_ARG[0]);
         Runner {
             repo_file_path: repo_file_path,
             status: 0,
@@ -64,6 +86,11 @@ impl<'a> Runner<'a> {
             include_arg_str: None,
             include_as_arg_str: None,
             exclude_arg_str: None,
+            ssh_key_path: ssh_key_path.map(|s| s.into()),
+            use_worktree: use_worktree,
+            worktree_dir: None,
+            git_global_args: Vec::new(),
+            backend: Box::new(ExecBackend),
             log_p: if is_dry_run { "   # " } else { "" },
             input_branch: None,
             output_branch: if let Some(branch_name) = output_branch {
@@ -74,73 +101,67 @@ impl<'a> Runner<'a> {
         }
     }
 
+    // swap in a different GitBackend, eg a MockBackend in tests
+    pub fn with_backend(mut self, backend: Box<dyn GitBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
     // get the current ref that this git repo is pointing to
     // save it for later
-    pub fn save_current_ref(mut self) -> Self {
+    pub fn save_current_ref(mut self) -> Result<Self> {
         self.repo_original_ref = match self.repo {
             Some(ref repo) => git_helpers::get_current_ref(repo),
             None => None,
         };
-        self
+        Ok(self)
     }
 
-    pub fn make_and_checkout_orphan_branch(mut self, orphan_branch: &str) -> Self {
+    pub fn make_and_checkout_orphan_branch(mut self, orphan_branch: &str) -> Result<Self> {
         if self.dry_run {
             println!("git checkout --orphan {}", orphan_branch);
             println!("git rm -rf . > /dev/null");
-            return self;
+            return Ok(self);
         }
 
         match self.repo {
             Some(ref r) => {
-                let success = git_helpers::make_orphan_branch_and_checkout(
+                git_helpers::make_orphan_branch_and_checkout(
                     orphan_branch,
                     r,
-                ).is_ok();
-                if ! success {
-                    panic!("Failed to checkout orphan branch");
-                }
+                ).map_err(Error::Git)?;
                 // on a new orphan branch our existing files appear in the stage
                 // we need to essentially do "git rm -rf ."
-                let success = git_helpers::remove_index_and_files(r).is_ok();
-                if ! success {
-                    panic!("Failed to remove git indexed files after making orphan");
-                }
+                git_helpers::remove_index_and_files(r).map_err(Error::Git)?;
             },
-            _ => panic!("Something went horribly wrong!"),
+            _ => return Err(Error::RepoNotFound("no repository loaded on Runner".into())),
         };
         if self.verbose {
             println!("{}created and checked out orphan branch {}", self.log_p, orphan_branch);
         }
 
-        self
+        Ok(self)
     }
 
-    // check the state of the git repository. exit if
+    // check the state of the git repository. error if
     // there are modified files, in the middle of a merge conflict
     // etc...
-    pub fn safe_to_proceed(self) -> Self {
+    pub fn safe_to_proceed(mut self) -> Result<Self> {
+        // a temporary worktree keeps the user's checkout untouched,
+        // so uncommitted changes there are not a problem
+        if self.use_worktree {
+            return Ok(self);
+        }
         // TODO: also check for other things like:
         // are there files staged? are we resolving a conflict?
         // im just too lazy right now, and this is the most likely scenario
-        let args = ["git", "ls-files", "--modified"];
-        let output = match exec_helpers::execute(&args) {
-            Ok(o) => match o.status {
-                0 => o.stdout,
-                _ => panic!("Failed to run ls-files: {}", o.stderr),
-            },
-            Err(e) => panic!("Failed to run ls-files: {}", e),
-        };
-        if ! output.is_empty() {
-            exit_with_message_and_status(
-                "You have modified changes. Please stash or commit your changes before running this command",
-                1
-            );
+        if ! self.backend.ls_files_modified(&self.git_global_args)?.is_empty() {
+            return Err(Error::DirtyWorkingTree);
         }
-        self
+        Ok(self)
     }
 
-    pub fn populate_empty_branch_with_remote_commits(self) -> Self {
+    pub fn populate_empty_branch_with_remote_commits(self) -> Result<Self> {
         let remote_repo = self.repo_file.remote_repo.clone();
         let remote_branch: Option<&str> = match &self.repo_file.remote_branch {
             Some(branch_name) => Some(branch_name.as_str()),
@@ -148,31 +169,46 @@ impl<'a> Runner<'a> {
         };
 
         match self.repo {
-            None => panic!("Failed to find repo?"),
+            None => return Err(Error::RepoNotFound("no repository loaded on Runner".into())),
             Some(ref r) => {
                 match (self.dry_run, &self.input_branch) {
                     (true, Some(branch_name)) => println!("git merge {}", branch_name),
-                    (true, None) => println!("git pull {}", remote_repo.unwrap()),
+                    (true, None) => println!(
+                        "git fetch {} {}",
+                        remote_repo.clone().unwrap_or("?".into()),
+                        remote_branch.clone().unwrap_or("".into()),
+                    ),
                     (false, Some(branch_name)) => {
                         println!("{}Merging {}", self.log_p, branch_name);
                         git_helpers::merge_branches(&r, &branch_name[..], None);
                     },
                     (false, None) => {
-                        println!("{}Pulling from {} {}", self.log_p, remote_repo.clone().unwrap_or("?".into()), remote_branch.clone().unwrap_or("".into()));
-                        git_helpers::pull(&r, &remote_repo.unwrap()[..], remote_branch);
+                        println!(
+                            "{}Fetching from {} {}",
+                            self.log_p,
+                            remote_repo.clone().unwrap_or("?".into()),
+                            remote_branch.clone().unwrap_or("".into()),
+                        );
+                        let annotated = fetch::fetch(
+                            r,
+                            &remote_repo.unwrap()[..],
+                            remote_branch,
+                            self.ssh_key_path.as_deref(),
+                        )?;
+                        fetch::merge_fetched_commit(r, &annotated)?;
                     },
                 };
             },
         };
-        self
+        Ok(self)
     }
 
-    pub fn rebase(mut self) -> Self {
+    pub fn rebase(mut self) -> Result<Self> {
         let upstream_branch = match self.repo_original_ref {
             Some(ref branch) => branch,
             None => {
                 println!("Failed to get repo original ref. Not going to rebase");
-                return self;
+                return Ok(self);
             },
         };
         let upstream_branch = upstream_branch.replace("refs/heads/", "");
@@ -186,112 +222,185 @@ impl<'a> Runner<'a> {
             // the below command implies: apply rebased changes in
             // the branch we are already on
             println!("git rebase {}", upstream_branch);
-            return self
+            return Ok(self)
         }
 
-        let args = [
-            "git", "rebase", upstream_branch.as_str(),
-        ];
-        let err_msg = match exec_helpers::execute(&args) {
-            Err(e) => Some(vec![format!("{}", e)]),
-            Ok(o) => {
-                match o.status {
-                    0 => None,
-                    _ => Some(vec![o.stderr.lines().next().unwrap().to_string()]),
-                }
-            },
-        };
-        if let Some(err) = err_msg {
+        if let Err(e) = self.backend.rebase(upstream_branch.as_str(), &self.git_global_args) {
             self.status = 1;
             let err_details = match self.verbose {
-                true => format!("{}", err.join("\n")),
+                true => format!("{}", e),
                 false => "".into(),
             };
             println!("Failed to rebase\n{}", err_details);
+            return Err(e);
         }
-        self
+        Ok(self)
     }
 
-    pub fn get_repo_file(mut self) -> Self {
+    pub fn get_repo_file(mut self) -> Result<Self> {
         // safe to unwrap because its required
         let repo_file_name = self.repo_file_path.unwrap();
         self.repo_file = repo_file::parse_repo_file(repo_file_name);
         if self.verbose {
             println!("{}got repo file: {}", self.log_p, repo_file_name);
         }
-        self
+        Ok(self)
     }
 
-    pub fn save_current_dir(mut self) -> Self {
+    pub fn save_current_dir(mut self) -> Result<Self> {
         // save this for later, as well as to find the repository
-        self.current_dir = match env::current_dir() {
-            Ok(pathbuf) => pathbuf,
-            Err(_) => panic!("Failed to find your current directory. Cannot proceed"),
-        };
+        self.current_dir = env::current_dir().map_err(|e| {
+            Error::RepoNotFound(format!("failed to find your current directory: {}", e))
+        })?;
         if self.verbose {
             println!("{}saving current dir to return to later: {}", self.log_p, self.current_dir.display());
         }
-        self
+        Ok(self)
     }
-    pub fn get_repository_from_current_dir(mut self) -> Self {
+    pub fn get_repository_from_current_dir(mut self) -> Result<Self> {
         let (repo, repo_path) = git_helpers::get_repository_and_root_directory(&self.current_dir);
         self.repo = Some(repo);
         self.repo_root_dir = repo_path;
         if self.verbose {
             println!("{}found repo path: {}", self.log_p, self.repo_root_dir.display());
         }
-        self
+        Ok(self)
     }
-    pub fn change_to_repo_root(self) -> Self {
+    // root every subsequent git invocation at the repo (or worktree)
+    // root via a persistent `-C <path>` global arg, instead of calling
+    // the process-global env::set_current_dir
+    pub fn change_to_repo_root(mut self) -> Result<Self> {
+        let target_dir = self.worktree_dir.clone().unwrap_or_else(|| self.repo_root_dir.clone());
+        let target_dir_str = target_dir.to_str().ok_or_else(|| {
+            Error::ChangeRepoRoot(format!("{:?}", &target_dir))
+        })?;
+
+        self.git_global_args = vec!["-C".into(), target_dir_str.into()];
+
         if self.dry_run {
-            println!("cd {}", self.repo_root_dir.display());
-            return self;
+            println!("git -C {} ...", target_dir_str);
+            return Ok(self);
+        }
+        if self.verbose {
+            println!("{}rooted git commands at {}", self.log_p, target_dir.display());
+        }
+        Ok(self)
+    }
+
+    // build a `git <global args...> <rest...>` argument list rooted at
+    // whatever change_to_repo_root last set
+    fn git_args(&self, rest: &[&str]) -> Vec<String> {
+        let mut args = vec!["git".to_string()];
+        args.extend(self.git_global_args.iter().cloned());
+        args.extend(rest.iter().map(|a| a.to_string()));
+        args
+    }
+
+    // when --worktree was passed, create a temporary linked worktree
+    // (`git worktree add --detach <tmp>`) and root the rest of the
+    // split there instead of in the user's current checkout
+    pub fn make_temporary_worktree(mut self) -> Result<Self> {
+        if ! self.use_worktree {
+            return Ok(self);
+        }
+
+        let tmp_dir = env::temp_dir().join(format!("git-monorepo-tools-worktree-{}", std::process::id()));
+        let tmp_dir_str = tmp_dir.to_str().unwrap_or_default();
+        let args = self.git_args(&["worktree", "add", "--detach", tmp_dir_str]);
+        let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        if self.dry_run {
+            println!("{}", arg_refs.join(" "));
+            // re-root subsequent dry-run printouts at the worktree too,
+            // even though it won't actually exist until a real run
+            self.git_global_args = vec!["-C".into(), tmp_dir_str.to_string()];
+            self.worktree_dir = Some(tmp_dir);
+            return Ok(self);
+        }
+
+        match exec_helpers::execute(&arg_refs) {
+            Ok(o) if o.status == 0 => (),
+            Ok(o) => return Err(Error::FilterRepoFailed {
+                args: args.clone(),
+                stderr: o.stderr,
+            }),
+            Err(e) => return Err(Error::FilterRepoFailed {
+                args: args.clone(),
+                stderr: format!("{}", e),
+            }),
+        };
+        if self.verbose {
+            println!("{}created temporary worktree at {}", self.log_p, tmp_dir.display());
         }
-        if ! changed_to_repo_root(&self.repo_root_dir) {
-            panic!("Failed to change to repository root: {:?}", &self.repo_root_dir);
+        // re-root subsequent git commands at the new worktree, including
+        // self.repo itself (make_and_checkout_orphan_branch and
+        // populate_empty_branch_with_remote_commits go through libgit2,
+        // not a shelled-out git command)
+        self.git_global_args = vec!["-C".into(), tmp_dir_str.to_string()];
+        self.repo = Some(open_repo_at(&tmp_dir)?);
+        self.worktree_dir = Some(tmp_dir);
+        Ok(self)
+    }
+
+    // remove the temporary worktree created by make_temporary_worktree,
+    // if one exists
+    pub fn remove_temporary_worktree(mut self) -> Result<Self> {
+        let tmp_dir = match self.worktree_dir.take() {
+            Some(dir) => dir,
+            None => return Ok(self),
+        };
+
+        let tmp_dir_str = tmp_dir.to_str().unwrap_or_default();
+        let args = self.git_args(&["worktree", "remove", tmp_dir_str, "--force"]);
+        let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        if self.dry_run {
+            println!("{}", arg_refs.join(" "));
+            return Ok(self);
         }
+
+        match exec_helpers::execute(&arg_refs) {
+            Ok(o) if o.status == 0 => (),
+            Ok(o) => return Err(Error::FilterRepoFailed {
+                args: args.clone(),
+                stderr: o.stderr,
+            }),
+            Err(e) => return Err(Error::FilterRepoFailed {
+                args: args.clone(),
+                stderr: format!("{}", e),
+            }),
+        };
         if self.verbose {
-            println!("{}changed to repository root {}", self.log_p, self.repo_root_dir.display());
+            println!("{}removed temporary worktree at {}", self.log_p, tmp_dir.display());
         }
-        self
+        Ok(self)
     }
 
-    // panic if all dependencies are not met
-    pub fn verify_dependencies(self) -> Self {
+    // error if all dependencies are not met
+    pub fn verify_dependencies(self) -> Result<Self> {
         if ! exec_helpers::executed_successfully(&["git", "--version"]) {
-            panic!("Failed to run. Missing dependency 'git'");
+            return Err(Error::MissingDependency("git".into()));
         }
         if ! exec_helpers::executed_successfully(&["git", "filter-repo", "--version"]) {
-            panic!("Failed to run. Missing dependency 'git-filter-repo'");
+            return Err(Error::MissingDependency("git-filter-repo".into()));
         }
-        self
+        Ok(self)
     }
-    pub fn run_filter(self, arg_vec: Vec<&str>, verbose_log: &str) -> Self {
+    pub fn run_filter(mut self, arg_vec: Vec<&str>, verbose_log: &str) -> Result<Self> {
         if self.dry_run {
             println!("{}", arg_vec.join(" "));
-            return self
+            return Ok(self)
         }
         if self.verbose {
             println!("{}", verbose_log);
         }
-        let err_msg = match exec_helpers::execute(&arg_vec) {
-            Ok(o) => match o.status {
-                0 => None,
-                _ => Some(o.stderr),
-            },
-            Err(e) => Some(format!("{}", e)),
-        };
-        if let Some(err) = err_msg {
-            panic!("Failed to execute: \"{}\"\n{}", arg_vec.join(" "), err);
-        }
+        self.backend.run_filter(&arg_vec)?;
 
-        self
+        Ok(self)
     }
 
-    pub fn filter_include(self) -> Self {
+    pub fn filter_include(self) -> Result<Self> {
         if self.include_arg_str.is_none() {
             // dont run filter if this arg was not provided
-            return self;
+            return Ok(self);
         }
         let output_branch_name = self.output_branch.clone().unwrap();
         let include_arg_str_opt = self.include_arg_str.clone();
@@ -299,14 +408,15 @@ impl<'a> Runner<'a> {
         let arg_vec = generate_filter_arg_vec(
             &include_arg_str,
             output_branch_name.as_str(),
+            &self.git_global_args,
         );
 
         self.run_filter(arg_vec, "Filtering include")
     }
-    pub fn filter_include_as(self) -> Self {
+    pub fn filter_include_as(self) -> Result<Self> {
         if self.include_as_arg_str.is_none() {
             // dont run filter if this arg was not provided
-            return self;
+            return Ok(self);
         }
         let output_branch_name = self.output_branch.clone().unwrap();
         let include_as_arg_str_opt = self.include_as_arg_str.clone();
@@ -314,14 +424,15 @@ impl<'a> Runner<'a> {
         let arg_vec = generate_filter_arg_vec(
             &include_as_arg_str,
             output_branch_name.as_str(),
+            &self.git_global_args,
         );
 
         self.run_filter(arg_vec, "Filtering include_as")
     }
-    pub fn filter_exclude(self) -> Self {
+    pub fn filter_exclude(self) -> Result<Self> {
         if self.exclude_arg_str.is_none() {
             // dont run filter if this arg was not provided
-            return self;
+            return Ok(self);
         }
         let output_branch_name = self.output_branch.clone().unwrap();
         let exclude_arg_str_opt = self.exclude_arg_str.clone();
@@ -329,22 +440,23 @@ impl<'a> Runner<'a> {
         let arg_vec = generate_filter_arg_vec(
             &exclude_arg_str,
             output_branch_name.as_str(),
+            &self.git_global_args,
         );
 
         self.run_filter(arg_vec, "Filtering exclude")
     }
 }
 
-pub fn exit_with_message_and_status(msg: &str, status: i32) {
-    println!("{}", msg);
-    std::process::exit(status);
-}
-
 pub fn generate_filter_arg_vec<'a>(
     args: &'a Vec<String>,
     output_branch: &'a str,
+    global_args: &'a Vec<String>,
 ) -> Vec<&'a str> {
-    let mut arg_vec = vec!["git", "filter-repo"];
+    let mut arg_vec = vec!["git"];
+    for global_arg in global_args {
+        arg_vec.push(global_arg);
+    }
+    arg_vec.push("filter-repo");
     for arg in args {
         arg_vec.push(arg);
     }
@@ -355,70 +467,19 @@ pub fn generate_filter_arg_vec<'a>(
     arg_vec
 }
 
-fn get_string_after_last_slash(s: String, slash_type: char) -> String {
-    let mut pieces = s.rsplit(slash_type);
-    match pieces.next() {
-        Some(p) => p.into(),
-        None => s.into(),
-    }
-}
-
-fn get_string_before_first_dot(s: String) -> String {
-    let mut pieces = s.split('.');
-    match pieces.next() {
-        Some(p) => p.into(),
-        None => s.into(),
-    }
-}
-
 pub fn is_valid_remote_repo(remote_repo: &String) -> bool {
-    // TODO:
-    // need to check for if it matches a regex like a server ip
-    // like 192.168.1.1, or user@server.com:/gitpath
-    return remote_repo.starts_with("ssh://") ||
-    remote_repo.starts_with("git://") ||
-    remote_repo.starts_with("http://") ||
-    remote_repo.starts_with("https://") ||
-    remote_repo.starts_with("ftp://") ||
-    remote_repo.starts_with("sftp://") ||
-    remote_repo.starts_with("file://") ||
-    remote_repo.starts_with(".") ||
-    remote_repo.starts_with("/");
+    remote_type::is_valid_remote_repo(remote_repo)
 }
 
 // try to parse the remote repo
-pub fn try_get_repo_name_from_remote_repo(remote_repo: String) -> String {
-    let slash_type = MAIN_SEPARATOR;
-    let next_slash_type = if slash_type == '/' { '\\' } else { '/' };
-
-    // try to use native slash first:
-    let mut repo_name = try_get_repo_name_with_slash_type(&remote_repo, slash_type);
-    if repo_name == "" {
-        repo_name = try_get_repo_name_with_slash_type(&remote_repo, next_slash_type);
-    }
-
-    if repo_name == "" {
-        panic!("Failed to parse repo_name from remote_repo: {}", remote_repo);
-    }
-
-    repo_name
-}
-
-pub fn try_get_repo_name_with_slash_type(remote_repo: &String, slash_type: char) -> String {
-    let mut out_str = remote_repo.clone().trim_end().to_string();
-    if !is_valid_remote_repo(&remote_repo) {
-        out_str = "".into();
-    }
-    if out_str.ends_with(slash_type) {
-        out_str.pop();
-    }
-    if !out_str.contains(slash_type) {
-        out_str = "".into();
-    }
-    out_str = get_string_after_last_slash(out_str, slash_type);
-    out_str = get_string_before_first_dot(out_str);
-
-    return out_str;
+pub fn try_get_repo_name_from_remote_repo(remote_repo: String) -> Result<String> {
+    let remote_kind = remote_type::parse_remote_type(&remote_repo).ok_or_else(|| {
+        Error::RemoteParse(format!("failed to parse repo_name from remote_repo: {}", remote_repo))
+    })?;
+
+    remote_type::repo_name_for_type(&remote_repo, remote_kind).ok_or_else(|| {
+        Error::RemoteParse(format!("failed to parse repo_name from remote_repo: {}", remote_repo))
+    })
 }
 
 // works for include, or include_as
@@ -435,24 +496,23 @@ pub fn include_var_valid(var: &Vec<String>, can_be_single: bool) -> bool {
     return false;
 }
 
-pub fn panic_if_array_invalid(var: &Option<Vec<String>>, can_be_single: bool, varname: &str) {
+pub fn check_array_valid(var: &Option<Vec<String>>, can_be_single: bool, varname: &str) -> Result<()> {
     match var {
         Some(v) => {
             if ! include_var_valid(&v, can_be_single) {
-                panic!("{} is invalid. Must be either a single string, or an even length array of strings", varname);
+                return Err(Error::InvalidArg(varname.to_string()));
             }
+            Ok(())
         },
-        _ => (),
-    };
-}
-
-pub fn changed_to_repo_root(repo_root: &PathBuf) -> bool {
-    match env::set_current_dir(repo_root) {
-        Ok(_) => true,
-        Err(_) => false,
+        _ => Ok(()),
     }
 }
 
+// reopen the repository at `path`, used by make_temporary_worktree to
+// rebind self.repo onto the worktree instead of the original checkout
+fn open_repo_at(path: &PathBuf) -> Result<git2::Repository> {
+    git2::Repository::open(path).map_err(Error::Git)
+}
 
 #[cfg(test)]
 mod test {
@@ -462,7 +522,7 @@ mod test {
     #[cfg(target_family = "unix")]
     fn unix_get_repo_name_from_remote_repo_should_try_main_seperator_first() {
         let my_remote_repo = "https://website.com/reponame".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
         assert_eq!(repo_name, "reponame");
     }
 
@@ -470,7 +530,7 @@ mod test {
     #[cfg(target_family = "unix")]
     fn unix_get_repo_name_from_remote_repo_should_try_main_seperator_first_with_dot() {
         let my_remote_repo = "https://website.com/reponame.git".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
         assert_eq!(repo_name, "reponame");
     }
 
@@ -478,7 +538,7 @@ mod test {
     #[cfg(target_family = "windows")]
     fn win_get_repo_name_from_remote_repo_should_try_main_seperator_first() {
         let my_remote_repo = "file://some\\path\\reponame".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
         assert_eq!(repo_name, "reponame");
     }
 
@@ -486,7 +546,7 @@ mod test {
     #[cfg(target_family = "unix")]
     fn unix_get_repo_name_from_remote_repo_should_use_other_path_slash_if_not_found() {
         let my_remote_repo = ".\\Desktop\\reponame".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
         assert_eq!(repo_name, "reponame");
     }
 
@@ -494,7 +554,7 @@ mod test {
     #[cfg(target_family = "windows")]
     fn win_get_repo_name_from_remote_repo_should_use_other_path_slash_if_not_found() {
         let my_remote_repo = "https://website.com/reponame".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
         assert_eq!(repo_name, "reponame");
     }
 
@@ -502,7 +562,121 @@ mod test {
     #[cfg(target_family = "windows")]
     fn win_get_repo_name_from_remote_repo_should_use_other_path_slash_if_not_found_with_dot() {
         let my_remote_repo = "https://website.com/reponame.git".into();
-        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo);
+        let repo_name = try_get_repo_name_from_remote_repo(my_remote_repo).unwrap();
+        assert_eq!(repo_name, "reponame");
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_ssh() {
+        let remote = "ssh://git@website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Ssh));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_scp_style_ssh() {
+        let remote = "git@github.com:nikita-skobov/git-monorepo-tools.git";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Ssh));
+        let repo_name = try_get_repo_name_from_remote_repo(remote.into()).unwrap();
+        assert_eq!(repo_name, "git-monorepo-tools");
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_scp_style_ssh_with_absolute_path() {
+        let remote = "user@server.com:/gitpath/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Ssh));
+        let repo_name = try_get_repo_name_from_remote_repo(remote.into()).unwrap();
         assert_eq!(repo_name, "reponame");
     }
+
+    #[test]
+    fn parse_remote_type_recognizes_https() {
+        let remote = "https://website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Https));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_http() {
+        let remote = "http://website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Http));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_git() {
+        let remote = "git://website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Git));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_ftp() {
+        let remote = "ftp://website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Ftp));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_sftp() {
+        let remote = "sftp://website.com/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Ftp));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_file() {
+        let remote = "file:///home/user/reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::File));
+    }
+
+    #[test]
+    fn parse_remote_type_recognizes_local() {
+        let remote = "../reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Local));
+    }
+
+    #[test]
+    fn parse_remote_type_does_not_mistake_windows_path_for_scp() {
+        let remote = ".\\Desktop\\reponame";
+        assert_eq!(remote_type::parse_remote_type(remote), Some(RemoteType::Local));
+    }
+
+    #[test]
+    fn open_repo_at_rebinds_to_the_worktree_not_the_main_repo() {
+        let main_dir = env::temp_dir().join(format!("gmt-test-main-{}", std::process::id()));
+        let worktree_dir = env::temp_dir().join(format!("gmt-test-worktree-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&main_dir);
+        let _ = std::fs::remove_dir_all(&worktree_dir);
+        let main_repo = git2::Repository::init(&main_dir).unwrap();
+        let worktree_repo = git2::Repository::init(&worktree_dir).unwrap();
+
+        // this is what make_temporary_worktree does once the worktree
+        // exists: rebind self.repo onto it instead of leaving it
+        // pointed at the original checkout
+        let reopened = open_repo_at(&worktree_dir).unwrap();
+
+        assert_ne!(reopened.path(), main_repo.path());
+        assert_eq!(reopened.path(), worktree_repo.path());
+
+        let _ = std::fs::remove_dir_all(&main_dir);
+        let _ = std::fs::remove_dir_all(&worktree_dir);
+    }
+
+    #[test]
+    fn check_array_valid_accepts_none() {
+        assert!(check_array_valid(&None, true, "include").is_ok());
+    }
+
+    #[test]
+    fn check_array_valid_accepts_a_single_item_when_allowed() {
+        let var = Some(vec!["subdir".to_string()]);
+        assert!(check_array_valid(&var, true, "include").is_ok());
+    }
+
+    #[test]
+    fn check_array_valid_rejects_a_single_item_when_not_allowed() {
+        let var = Some(vec!["subdir".to_string()]);
+        assert!(check_array_valid(&var, false, "include").is_err());
+    }
+
+    #[test]
+    fn check_array_valid_rejects_an_odd_length_array() {
+        let var = Some(vec!["subdir".to_string(), "as_dir".to_string(), "extra".to_string()]);
+        assert!(check_array_valid(&var, true, "include").is_err());
+    }
 }